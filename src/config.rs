@@ -0,0 +1,28 @@
+/// The global, site-wide configuration, usually loaded from `config.toml`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Config {
+    /// Base URL of the site, the only required config argument
+    pub base_url: String,
+    /// Title of the site
+    pub title: Option<String>,
+}
+
+impl Config {
+    /// Makes a permalink from a path relative to the site root, taking into account whether
+    /// `base_url` already ends with a slash
+    pub fn make_permalink(&self, path: &str) -> String {
+        let trailing_bit = if path.ends_with('/') || path.is_empty() { "" } else { "/" };
+
+        if path.is_empty() {
+            if self.base_url.ends_with('/') {
+                self.base_url.clone()
+            } else {
+                format!("{}/", self.base_url)
+            }
+        } else if self.base_url.ends_with('/') {
+            format!("{}{}{}", self.base_url, path, trailing_bit)
+        } else {
+            format!("{}/{}{}", self.base_url, path, trailing_bit)
+        }
+    }
+}
@@ -0,0 +1,7 @@
+error_chain! {
+    foreign_links {
+        Io(::std::io::Error);
+        Tera(::tera::Error);
+        Toml(::toml::de::Error);
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use errors::{Result, ResultExt};
+
+/// The front matter every page and section can have, extracted from the TOML block
+/// delimited by `+++` at the top of each `.md` file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FrontMatter {
+    /// <title> of the page/section
+    pub title: Option<String>,
+    /// Description in <meta> that appears when linked, e.g. on twitter
+    pub description: Option<String>,
+    /// Template to use for that page/section. Defaults to `page.html`/`section.html`, or
+    /// `index.html` for the site index
+    pub template: Option<String>,
+    /// Split a section's pages into pagers of this many pages each. Only makes sense on
+    /// sections, ignored on pages
+    pub paginate: Option<usize>,
+    /// How a section should sort its direct pages, defaults to not sorting at all
+    #[serde(default)]
+    pub sort_by: SortBy,
+    /// The date of the page/section, in `YYYY-MM-DD` format, used when sorting by `date`
+    pub date: Option<String>,
+    /// The weight of the page/section, used when sorting by `weight`. Lower sorts first
+    pub weight: Option<usize>,
+    /// Whether a section should emit an Atom feed of its pages at `atom.xml`. Only makes
+    /// sense on sections, ignored on pages
+    #[serde(default)]
+    pub generate_feed: bool,
+}
+
+/// How a `Section` should order its `pages`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortBy {
+    /// Most recent `date` first, pages without one are ignored
+    Date,
+    /// Lowest `weight` first, pages without one are ignored
+    Weight,
+    /// Keep the order pages were found in, sorting nothing
+    None,
+}
+
+impl Default for SortBy {
+    fn default() -> SortBy {
+        SortBy::None
+    }
+}
+
+impl FrontMatter {
+    pub fn parse(toml: &str) -> Result<FrontMatter> {
+        let meta: FrontMatter = ::toml::from_str(toml)
+            .chain_err(|| "Failed to parse front matter")?;
+
+        Ok(meta)
+    }
+}
+
+impl Default for FrontMatter {
+    fn default() -> FrontMatter {
+        FrontMatter {
+            title: None,
+            description: None,
+            template: None,
+            paginate: None,
+            sort_by: SortBy::None,
+            date: None,
+            weight: None,
+            generate_feed: false,
+        }
+    }
+}
+
+/// Splits a file between the front matter and its content, parsing the former
+pub fn split_content(file_path: &Path, content: &str) -> Result<(FrontMatter, String)> {
+    if !content.starts_with("+++\n") {
+        bail!("Couldn't find front matter in `{}`, did you forget to add `+++`?", file_path.display());
+    }
+
+    let mut splits = content.splitn(3, "+++\n");
+    splits.next(); // the first split is empty
+    let front_matter = splits.next().unwrap();
+    let content = splits.next().unwrap_or("");
+
+    let meta = FrontMatter::parse(front_matter)
+        .chain_err(|| format!("Error parsing front matter of file `{}`", file_path.display()))?;
+
+    Ok((meta, content.to_string()))
+}
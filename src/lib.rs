@@ -0,0 +1,15 @@
+#[macro_use]
+extern crate error_chain;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate tera;
+extern crate toml;
+
+pub mod errors;
+pub mod utils;
+pub mod config;
+pub mod front_matter;
+pub mod page;
+pub mod section;
+pub mod pagination;
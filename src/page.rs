@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+use std::result::Result as StdResult;
+
+use serde::ser::{SerializeStruct, self};
+
+use config::Config;
+use front_matter::{FrontMatter, split_content};
+use errors::{Result, ResultExt};
+use utils::{read_file, find_content_components};
+
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Page {
+    /// The full path to the `.md` file
+    pub file_path: PathBuf,
+    /// The `.md` path, starting from the content directory, with `/` slashes
+    pub relative_path: String,
+    /// The folder names from `content` to this page file
+    pub components: Vec<String>,
+    /// The URL path of the page
+    pub path: String,
+    /// The full URL for that page
+    pub permalink: String,
+    /// The front matter meta-data
+    pub meta: FrontMatter,
+    /// The actual content of the page, in markdown
+    pub raw_content: String,
+    /// The previous page in the section, according to the section's sort order.
+    /// A lightweight copy whose own `previous`/`next` are always `None`
+    pub previous: Option<Box<Page>>,
+    /// The next page in the section, according to the section's sort order.
+    /// A lightweight copy whose own `previous`/`next` are always `None`
+    pub next: Option<Box<Page>>,
+}
+
+impl Page {
+    pub fn new<P: AsRef<Path>>(file_path: P, meta: FrontMatter) -> Page {
+        let file_path = file_path.as_ref();
+
+        Page {
+            file_path: file_path.to_path_buf(),
+            relative_path: "".to_string(),
+            components: vec![],
+            path: "".to_string(),
+            permalink: "".to_string(),
+            meta: meta,
+            raw_content: "".to_string(),
+            previous: None,
+            next: None,
+        }
+    }
+
+    pub fn parse(file_path: &Path, content: &str, config: &Config) -> Result<Page> {
+        let (meta, raw_content) = split_content(file_path, content)?;
+        let mut page = Page::new(file_path, meta);
+        page.raw_content = raw_content;
+        page.components = find_content_components(file_path);
+        let file_stem = file_path.file_stem().unwrap().to_string_lossy().to_string();
+        page.path = if page.components.is_empty() {
+            file_stem
+        } else {
+            format!("{}/{}", page.components.join("/"), file_stem)
+        };
+        page.permalink = config.make_permalink(&page.path);
+        page.relative_path = format!("{}.md", page.path);
+
+        Ok(page)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P, config: &Config) -> Result<Page> {
+        let path = path.as_ref();
+        let content = read_file(path)?;
+
+        Page::parse(path, &content, config)
+    }
+}
+
+impl ser::Serialize for Page {
+    fn serialize<S>(&self, serializer: S) -> StdResult<S::Ok, S::Error> where S: ser::Serializer {
+        let mut state = serializer.serialize_struct("page", 8)?;
+        state.serialize_field("title", &self.meta.title)?;
+        state.serialize_field("description", &self.meta.description)?;
+        state.serialize_field("path", &format!("/{}", self.path))?;
+        state.serialize_field("permalink", &self.permalink)?;
+        state.serialize_field("date", &self.meta.date)?;
+        state.serialize_field("content", &self.raw_content)?;
+        state.serialize_field("previous", &self.previous)?;
+        state.serialize_field("next", &self.next)?;
+        state.end()
+    }
+}
@@ -0,0 +1,236 @@
+use tera::{Tera, Context};
+use serde::ser::{SerializeStruct, self};
+use std::result::Result as StdResult;
+
+use config::Config;
+use errors::{Result, ResultExt};
+use page::Page;
+use section::Section;
+
+
+/// A single page of a `Paginator`, ready to be added to a Tera `Context` under the
+/// `paginator` key
+#[derive(Clone, Debug)]
+pub struct Pager<'a> {
+    /// 1-based index of this pager
+    pub index: usize,
+    /// How many pagers this section has in total
+    pub number_of_pages: usize,
+    /// The pages belonging to this pager
+    pub pages: Vec<&'a Page>,
+    /// Permalink to this pager
+    pub permalink: String,
+    /// Permalink to the previous pager, if any
+    pub previous: Option<String>,
+    /// Permalink to the next pager, if any
+    pub next: Option<String>,
+    /// Permalink to the first pager
+    pub first: String,
+    /// Permalink to the last pager
+    pub last: String,
+}
+
+impl<'a> ser::Serialize for Pager<'a> {
+    fn serialize<S>(&self, serializer: S) -> StdResult<S::Ok, S::Error> where S: ser::Serializer {
+        let mut state = serializer.serialize_struct("paginator", 7)?;
+        state.serialize_field("pages", &self.pages)?;
+        state.serialize_field("current_index", &self.index)?;
+        state.serialize_field("number_of_pages", &self.number_of_pages)?;
+        state.serialize_field("previous", &self.previous)?;
+        state.serialize_field("next", &self.next)?;
+        state.serialize_field("first", &self.first)?;
+        state.serialize_field("last", &self.last)?;
+        state.end()
+    }
+}
+
+/// Takes a `Section` whose front matter has `paginate` set and slices its `pages` into
+/// fixed-size `Pager`s, one of which gets rendered to its own HTML file
+#[derive(Debug)]
+pub struct Paginator<'a> {
+    /// How many pages go in each pager
+    paginate_by: usize,
+    /// The section being paginated
+    section: &'a Section,
+    /// The pagers, in order, index 0 being page 1
+    pub pagers: Vec<Pager<'a>>,
+}
+
+impl<'a> Paginator<'a> {
+    /// Builds a `Paginator` from a section. Should only be called when `section.meta.paginate`
+    /// is `Some`
+    pub fn from_section(section: &'a Section, config: &Config) -> Paginator<'a> {
+        // `paginate = 0` is a syntactically valid but meaningless front-matter value (a typo
+        // for "unlimited"); treat it the same as "not set" rather than dividing by zero
+        let paginate_by = section.meta.paginate
+            .filter(|&n| n > 0)
+            .unwrap_or(section.pages.len().max(1));
+        let number_of_pages = (section.pages.len() as f64 / paginate_by as f64).ceil().max(1.0) as usize;
+
+        let permalink_for = |index: usize| -> String {
+            if index == 1 {
+                section.permalink.clone()
+            } else {
+                config.make_permalink(&format!("{}/page/{}", section.path, index))
+            }
+        };
+        let first = permalink_for(1);
+        let last = permalink_for(number_of_pages);
+
+        let mut pagers = Vec::with_capacity(number_of_pages);
+        if section.pages.is_empty() {
+            // `chunks` yields nothing for an empty slice, but an empty section still needs
+            // its one (empty) page 1 rendered
+            pagers.push(Pager {
+                index: 1,
+                number_of_pages,
+                pages: vec![],
+                permalink: permalink_for(1),
+                previous: None,
+                next: None,
+                first: first.clone(),
+                last: last.clone(),
+            });
+        } else {
+            for chunk in section.pages.chunks(paginate_by) {
+                let index = pagers.len() + 1;
+                pagers.push(Pager {
+                    index,
+                    number_of_pages,
+                    pages: chunk.iter().collect(),
+                    permalink: permalink_for(index),
+                    previous: if index == 1 { None } else { Some(permalink_for(index - 1)) },
+                    next: if index == number_of_pages { None } else { Some(permalink_for(index + 1)) },
+                    first: first.clone(),
+                    last: last.clone(),
+                });
+            }
+        }
+
+        Paginator { paginate_by, section, pagers }
+    }
+
+    /// The path, relative to the output directory, a given pager should be written to.
+    /// Page 1 stays at the section's own path; later pages go under `page/{n}`
+    pub fn output_path(&self, pager: &Pager) -> String {
+        if pager.index == 1 {
+            format!("{}/index.html", self.section.path)
+        } else {
+            format!("{}/page/{}/index.html", self.section.path, pager.index)
+        }
+    }
+
+    /// Renders every pager, returning `(output_path, html)` pairs ready to be written to disk.
+    ///
+    /// `sections` is only used when paginating the index section, whose `index.html` template
+    /// lists the site's top-level sections the same way `Section::render_html` does
+    pub fn render_pagers(&self, sections: &[&Section], tera: &Tera, config: &Config) -> Result<Vec<(String, String)>> {
+        let tpl_name = self.section.get_template_name();
+
+        self.pagers.iter().map(|pager| {
+            let mut context = Context::new();
+            context.add("config", config);
+            context.add("section", self.section);
+            context.add("current_url", &pager.permalink);
+            context.add("current_path", &self.section.path);
+            context.add("paginator", pager);
+            if self.section.is_index() {
+                context.add("sections", &sections);
+            }
+
+            let html = tera.render(&tpl_name, &context)
+                .chain_err(|| format!("Failed to render pager {} of section '{}'", pager.index, self.section.file_path.display()))?;
+
+            Ok((self.output_path(pager), html))
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use config::Config;
+    use front_matter::FrontMatter;
+    use page::Page;
+    use section::Section;
+
+    use super::Paginator;
+
+    fn test_config() -> Config {
+        Config { base_url: "https://example.com".to_string(), title: None }
+    }
+
+    fn test_page(slug: &str) -> Page {
+        let mut page = Page::new(PathBuf::from("content/posts/post.md"), FrontMatter::default());
+        page.permalink = format!("https://example.com/posts/{}/", slug);
+        page
+    }
+
+    fn test_section(paginate: Option<usize>, num_pages: usize) -> Section {
+        let mut section = Section::new(PathBuf::from("content/posts/_index.md"), FrontMatter::default());
+        section.meta.paginate = paginate;
+        section.path = "posts".to_string();
+        section.permalink = "https://example.com/posts/".to_string();
+        section.pages = (0..num_pages).map(|i| test_page(&i.to_string())).collect();
+        section
+    }
+
+    #[test]
+    fn exact_multiple_splits_into_even_pagers() {
+        let section = test_section(Some(2), 4);
+        let paginator = Paginator::from_section(&section, &test_config());
+
+        assert_eq!(paginator.pagers.len(), 2);
+        assert_eq!(paginator.pagers[0].pages.len(), 2);
+        assert_eq!(paginator.pagers[1].pages.len(), 2);
+    }
+
+    #[test]
+    fn remainder_gets_its_own_trailing_pager() {
+        let section = test_section(Some(2), 5);
+        let paginator = Paginator::from_section(&section, &test_config());
+
+        assert_eq!(paginator.pagers.len(), 3);
+        assert_eq!(paginator.pagers[2].pages.len(), 1);
+        assert_eq!(paginator.pagers[0].previous, None);
+        assert_eq!(paginator.pagers[2].next, None);
+    }
+
+    #[test]
+    fn paginate_by_bigger_than_pages_yields_a_single_pager() {
+        let section = test_section(Some(50), 3);
+        let paginator = Paginator::from_section(&section, &test_config());
+
+        assert_eq!(paginator.pagers.len(), 1);
+        assert_eq!(paginator.pagers[0].pages.len(), 3);
+    }
+
+    #[test]
+    fn empty_section_still_gets_one_pager() {
+        let section = test_section(Some(2), 0);
+        let paginator = Paginator::from_section(&section, &test_config());
+
+        assert_eq!(paginator.pagers.len(), 1);
+        assert!(paginator.pagers[0].pages.is_empty());
+    }
+
+    #[test]
+    fn paginate_zero_does_not_panic_or_divide_by_zero() {
+        let section = test_section(Some(0), 5);
+        let paginator = Paginator::from_section(&section, &test_config());
+
+        // `0` is treated like "not set": everything fits in a single pager
+        assert_eq!(paginator.pagers.len(), 1);
+        assert_eq!(paginator.pagers[0].pages.len(), 5);
+    }
+
+    #[test]
+    fn first_and_last_permalinks_are_consistent() {
+        let section = test_section(Some(2), 5);
+        let paginator = Paginator::from_section(&section, &test_config());
+
+        assert_eq!(paginator.pagers[0].first, paginator.pagers[0].permalink);
+        assert_eq!(paginator.pagers[2].last, paginator.pagers[2].permalink);
+    }
+}
@@ -5,12 +5,71 @@ use tera::{Tera, Context};
 use serde::ser::{SerializeStruct, self};
 
 use config::Config;
-use front_matter::{FrontMatter, split_content};
+use front_matter::{FrontMatter, SortBy, split_content};
 use errors::{Result, ResultExt};
 use utils::{read_file, find_content_components};
 use page::{Page};
 
 
+/// Derives the permalinks of every ancestor of the section whose path is `components`, from
+/// the site root down to (but excluding) that section itself.
+///
+/// Walks each successive prefix of `components` (starting with the empty, site-root prefix)
+/// and looks up the section whose own `components` match it in `all_sections`. A prefix with
+/// no matching section (e.g. an intermediate directory with no `_index.md`) is skipped rather
+/// than breaking the chain.
+fn find_ancestors(components: &[String], all_sections: &[&Section]) -> Vec<String> {
+    let mut ancestors = Vec::new();
+
+    for i in 0..components.len() {
+        let prefix = &components[0..i];
+        if let Some(section) = all_sections.iter().find(|s| s.components.as_slice() == prefix) {
+            ancestors.push(section.permalink.clone());
+        }
+    }
+
+    ancestors
+}
+
+/// Turns a front matter `date` (`YYYY-MM-DD`) into the RFC 3339 timestamp Atom readers expect
+fn to_rfc3339(date: &str) -> String {
+    format!("{}T00:00:00Z", date)
+}
+
+/// A single `<entry>` for `Section::render_feed`, with its date already in RFC 3339 and
+/// built independently from `Section`'s own `Serialize` impl
+#[derive(Clone, Debug, Serialize)]
+struct FeedEntry<'a> {
+    title: &'a Option<String>,
+    description: &'a Option<String>,
+    permalink: &'a str,
+    date: Option<String>,
+    /// The page's raw, unrendered Markdown. There is no Markdown->HTML renderer in this
+    /// codebase yet, so `templates/atom.xml` emits this as `type="text"`, not `type="html"`
+    content: &'a str,
+}
+
+impl<'a> FeedEntry<'a> {
+    fn from_page(page: &'a Page) -> FeedEntry<'a> {
+        FeedEntry {
+            title: &page.meta.title,
+            description: &page.meta.description,
+            permalink: &page.permalink,
+            date: page.meta.date.as_ref().map(|d| to_rfc3339(d)),
+            content: &page.raw_content,
+        }
+    }
+}
+
+/// The most recent front-matter `date` among `pages`, in RFC 3339, for the feed's `<updated>`.
+/// Correct regardless of `sort_by`, unlike assuming `pages[0]` is the newest
+fn latest_update(pages: &[Page]) -> Option<String> {
+    pages.iter()
+        .filter_map(|p| p.meta.date.as_ref())
+        .max()
+        .map(|d| to_rfc3339(d))
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Section {
     /// The _index.md full path
@@ -25,11 +84,16 @@ pub struct Section {
     pub path: String,
     /// The full URL for that page
     pub permalink: String,
+    /// The permalinks of this section's ancestors, ordered from the site root down to
+    /// (but excluding) this section. Set while the section tree is assembled
+    pub ancestors: Vec<String>,
     /// The front matter meta-data
     pub meta: FrontMatter,
-    /// All direct pages of that section
+    /// All direct pages of that section, ordered according to `meta.sort_by` once
+    /// `sort_pages` has run, with `previous`/`next` set once `populate_siblings` has run
     pub pages: Vec<Page>,
-    /// All pages that cannot be sorted in this section
+    /// All pages that are missing the front matter key `meta.sort_by` relies on, in the
+    /// order they were found
     pub ignored_pages: Vec<Page>,
     /// All direct subsections
     pub subsections: Vec<Section>,
@@ -46,6 +110,7 @@ impl Section {
             components: vec![],
             path: "".to_string(),
             permalink: "".to_string(),
+            ancestors: vec![],
             meta: meta,
             pages: vec![],
             ignored_pages: vec![],
@@ -76,6 +141,73 @@ impl Section {
         Section::parse(path, &content, config)
     }
 
+    /// Sorts `pages` according to `meta.sort_by`, moving any page missing the relevant
+    /// front matter key into `ignored_pages` instead.
+    ///
+    /// Must run once all of a section's pages have been attached to it and before
+    /// `render_html`, so templates iterating `section.pages` see a deterministic order.
+    pub fn sort_pages(&mut self) {
+        let pages = ::std::mem::replace(&mut self.pages, vec![]);
+
+        let (mut sorted, mut unsortable): (Vec<Page>, Vec<Page>) = match self.meta.sort_by {
+            SortBy::Date => pages.into_iter().partition(|p| p.meta.date.is_some()),
+            SortBy::Weight => pages.into_iter().partition(|p| p.meta.weight.is_some()),
+            SortBy::None => (pages, vec![]),
+        };
+
+        match self.meta.sort_by {
+            // Newest first
+            SortBy::Date => sorted.sort_by(|a, b| b.meta.date.cmp(&a.meta.date)),
+            SortBy::Weight => sorted.sort_by(|a, b| a.meta.weight.cmp(&b.meta.weight)),
+            SortBy::None => (),
+        }
+
+        self.pages = sorted;
+        self.ignored_pages.append(&mut unsortable);
+    }
+
+    /// Derives this section's ancestor permalinks from `components`, from the site root down
+    /// to (but excluding) this section, by looking up each successive prefix of `components`
+    /// in `all_sections`. Called while the section tree is assembled, once every section's
+    /// `components`/`permalink` are known
+    pub fn find_ancestors(&self, all_sections: &[&Section]) -> Vec<String> {
+        find_ancestors(&self.components, all_sections)
+    }
+
+    /// Sets the permalinks of this section's ancestors directly, bypassing `find_ancestors`.
+    /// Mostly useful in tests and for themes building a section tree of their own
+    pub fn set_ancestors(&mut self, ancestors: Vec<String>) {
+        self.ancestors = ancestors;
+    }
+
+    /// Assigns `previous`/`next` on each page of `pages`, according to the order left by
+    /// `sort_pages`. Must run after `sort_pages`
+    pub fn populate_siblings(&mut self) {
+        // Snapshot the original, unmutated pages so each page's previous/next is built from
+        // a clone of its actual neighbour, not from a neighbour we've already mutated in this
+        // same pass (which would nest the whole sibling chain instead of stopping at one level)
+        let original = self.pages.clone();
+
+        for (i, page) in self.pages.iter_mut().enumerate() {
+            page.previous = if i > 0 {
+                let mut previous = original[i - 1].clone();
+                previous.previous = None;
+                previous.next = None;
+                Some(Box::new(previous))
+            } else {
+                None
+            };
+            page.next = if i + 1 < original.len() {
+                let mut next = original[i + 1].clone();
+                next.previous = None;
+                next.next = None;
+                Some(Box::new(next))
+            } else {
+                None
+            };
+        }
+    }
+
     pub fn get_template_name(&self) -> String {
         match self.meta.template {
             Some(ref l) => l.to_string(),
@@ -88,7 +220,16 @@ impl Section {
         }
     }
 
+    /// Whether this section should be split into several pages, one per `Paginator` pager,
+    /// instead of being rendered as a single file by `render_html`
+    pub fn is_paginated(&self) -> bool {
+        self.meta.paginate.is_some()
+    }
+
     /// Renders the page using the default layout, unless specified in front-matter
+    ///
+    /// Sections with `paginate` set in their front matter should be rendered through
+    /// `pagination::Paginator` instead, which calls this same template once per pager
     pub fn render_html(&self, sections: &[&Section], tera: &Tera, config: &Config) -> Result<String> {
         let tpl_name = self.get_template_name();
 
@@ -110,6 +251,30 @@ impl Section {
         self.components.is_empty()
     }
 
+    /// Whether this section should emit an Atom feed of its pages at `atom.xml`
+    pub fn generates_feed(&self) -> bool {
+        self.meta.generate_feed
+    }
+
+    /// Renders this section's pages as an Atom feed using the built-in `atom.xml` template,
+    /// which themes can override. `pages` is used as-is, so it should already have gone
+    /// through `sort_pages`. Entry content is the page's raw Markdown, not rendered HTML —
+    /// there's no Markdown->HTML renderer in this codebase yet
+    pub fn render_feed(&self, config: &Config, tera: &Tera) -> Result<String> {
+        let entries: Vec<FeedEntry> = self.pages.iter().map(FeedEntry::from_page).collect();
+        let last_updated = latest_update(&self.pages);
+
+        let mut context = Context::new();
+        context.add("config", config);
+        context.add("section", self);
+        context.add("entries", &entries);
+        context.add("last_updated", &last_updated);
+        context.add("feed_url", &config.make_permalink(&format!("{}/atom.xml", self.path)));
+
+        tera.render("atom.xml", &context)
+            .chain_err(|| format!("Failed to render feed for section '{}'", self.file_path.display()))
+    }
+
     pub fn all_pages_path(&self) -> Vec<PathBuf> {
         let mut paths = vec![];
         paths.extend(self.pages.iter().map(|p| p.file_path.clone()));
@@ -120,13 +285,220 @@ impl Section {
 
 impl ser::Serialize for Section {
     fn serialize<S>(&self, serializer: S) -> StdResult<S::Ok, S::Error> where S: ser::Serializer {
-        let mut state = serializer.serialize_struct("section", 6)?;
+        let mut state = serializer.serialize_struct("section", 7)?;
         state.serialize_field("title", &self.meta.title)?;
         state.serialize_field("description", &self.meta.description)?;
         state.serialize_field("path", &format!("/{}", self.path))?;
         state.serialize_field("permalink", &self.permalink)?;
+        state.serialize_field("ancestors", &self.ancestors)?;
         state.serialize_field("pages", &self.pages)?;
         state.serialize_field("subsections", &self.subsections)?;
         state.end()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use front_matter::{FrontMatter, SortBy};
+    use page::Page;
+
+    use super::Section;
+
+    fn test_section(sort_by: SortBy) -> Section {
+        let mut meta = FrontMatter::default();
+        meta.sort_by = sort_by;
+        Section::new(PathBuf::from("content/posts/_index.md"), meta)
+    }
+
+    fn test_page(date: Option<&str>, weight: Option<usize>) -> Page {
+        let mut meta = FrontMatter::default();
+        meta.date = date.map(|d| d.to_string());
+        meta.weight = weight;
+        Page::new(PathBuf::from("content/posts/post.md"), meta)
+    }
+
+    #[test]
+    fn sort_by_date_orders_newest_first_and_ignores_undated_pages() {
+        let mut section = test_section(SortBy::Date);
+        section.pages = vec![
+            test_page(Some("2018-01-01"), None),
+            test_page(Some("2018-03-01"), None),
+            test_page(None, None),
+            test_page(Some("2018-02-01"), None),
+        ];
+
+        section.sort_pages();
+
+        assert_eq!(section.pages.len(), 3);
+        assert_eq!(section.pages[0].meta.date, Some("2018-03-01".to_string()));
+        assert_eq!(section.pages[1].meta.date, Some("2018-02-01".to_string()));
+        assert_eq!(section.pages[2].meta.date, Some("2018-01-01".to_string()));
+        assert_eq!(section.ignored_pages.len(), 1);
+        assert_eq!(section.ignored_pages[0].meta.date, None);
+    }
+
+    #[test]
+    fn sort_by_weight_orders_ascending_and_ignores_unweighted_pages() {
+        let mut section = test_section(SortBy::Weight);
+        section.pages = vec![
+            test_page(None, Some(30)),
+            test_page(None, None),
+            test_page(None, Some(10)),
+            test_page(None, Some(20)),
+        ];
+
+        section.sort_pages();
+
+        assert_eq!(section.pages.len(), 3);
+        assert_eq!(section.pages[0].meta.weight, Some(10));
+        assert_eq!(section.pages[1].meta.weight, Some(20));
+        assert_eq!(section.pages[2].meta.weight, Some(30));
+        assert_eq!(section.ignored_pages.len(), 1);
+        assert_eq!(section.ignored_pages[0].meta.weight, None);
+    }
+
+    #[test]
+    fn sort_by_none_keeps_pages_as_found_and_ignores_nothing() {
+        let mut section = test_section(SortBy::None);
+        section.pages = vec![
+            test_page(Some("2018-01-01"), None),
+            test_page(None, None),
+            test_page(Some("2018-03-01"), None),
+        ];
+
+        section.sort_pages();
+
+        assert_eq!(section.pages.len(), 3);
+        assert!(section.ignored_pages.is_empty());
+        assert_eq!(section.pages[0].meta.date, Some("2018-01-01".to_string()));
+        assert_eq!(section.pages[1].meta.date, None);
+        assert_eq!(section.pages[2].meta.date, Some("2018-03-01".to_string()));
+    }
+
+    #[test]
+    fn sort_pages_appends_to_existing_ignored_pages() {
+        let mut section = test_section(SortBy::Date);
+        section.ignored_pages = vec![test_page(None, None)];
+        section.pages = vec![test_page(None, None), test_page(Some("2018-01-01"), None)];
+
+        section.sort_pages();
+
+        assert_eq!(section.pages.len(), 1);
+        assert_eq!(section.ignored_pages.len(), 2);
+    }
+
+    #[test]
+    fn populate_siblings_links_immediate_neighbours_only() {
+        let mut section = test_section(SortBy::None);
+        section.pages = vec![
+            test_page(Some("2018-01-01"), None),
+            test_page(Some("2018-02-01"), None),
+            test_page(Some("2018-03-01"), None),
+        ];
+
+        section.populate_siblings();
+
+        assert!(section.pages[0].previous.is_none());
+        assert_eq!(section.pages[0].next.as_ref().unwrap().meta.date, Some("2018-02-01".to_string()));
+
+        assert_eq!(section.pages[1].previous.as_ref().unwrap().meta.date, Some("2018-01-01".to_string()));
+        assert_eq!(section.pages[1].next.as_ref().unwrap().meta.date, Some("2018-03-01".to_string()));
+
+        assert!(section.pages[2].next.is_none());
+
+        // The sibling copies must be leaves: their own previous/next stay None, otherwise
+        // every page's serialized size would grow with its index in the section
+        assert!(section.pages[2].previous.as_ref().unwrap().previous.is_none());
+        assert!(section.pages[2].previous.as_ref().unwrap().next.is_none());
+        assert!(section.pages[1].previous.as_ref().unwrap().next.is_none());
+        assert!(section.pages[1].next.as_ref().unwrap().previous.is_none());
+    }
+
+    #[test]
+    fn set_ancestors_stores_permalinks_as_given() {
+        let mut section = test_section(SortBy::None);
+        section.set_ancestors(vec!["https://example.com/".to_string()]);
+
+        assert_eq!(section.ancestors, vec!["https://example.com/".to_string()]);
+    }
+
+    fn test_section_at(components: &[&str], permalink: &str) -> Section {
+        let mut section = test_section(SortBy::None);
+        section.components = components.iter().map(|c| c.to_string()).collect();
+        section.permalink = permalink.to_string();
+        section
+    }
+
+    #[test]
+    fn find_ancestors_walks_root_to_direct_parent() {
+        let root = test_section_at(&[], "https://example.com/");
+        let blog = test_section_at(&["blog"], "https://example.com/blog/");
+        let rust = test_section_at(&["blog", "rust"], "https://example.com/blog/rust/");
+        let all_sections = vec![&root, &blog, &rust];
+
+        let ancestors = rust.find_ancestors(&all_sections);
+
+        assert_eq!(ancestors, vec![
+            "https://example.com/".to_string(),
+            "https://example.com/blog/".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn find_ancestors_on_a_top_level_section_is_just_the_root() {
+        let root = test_section_at(&[], "https://example.com/");
+        let blog = test_section_at(&["blog"], "https://example.com/blog/");
+        let all_sections = vec![&root, &blog];
+
+        assert_eq!(blog.find_ancestors(&all_sections), vec!["https://example.com/".to_string()]);
+    }
+
+    #[test]
+    fn find_ancestors_on_the_root_section_is_empty() {
+        let root = test_section_at(&[], "https://example.com/");
+        let all_sections = vec![&root];
+
+        assert!(root.find_ancestors(&all_sections).is_empty());
+    }
+
+    #[test]
+    fn find_ancestors_skips_intermediate_paths_with_no_section() {
+        // No `_index.md` (and therefore no `Section`) for `blog/2018`
+        let root = test_section_at(&[], "https://example.com/");
+        let post = test_section_at(&["blog", "2018", "my-post"], "https://example.com/blog/2018/my-post/");
+        let all_sections = vec![&root, &post];
+
+        assert_eq!(post.find_ancestors(&all_sections), vec!["https://example.com/".to_string()]);
+    }
+
+    #[test]
+    fn latest_update_is_the_max_date_regardless_of_page_order() {
+        let pages = vec![
+            test_page(Some("2018-02-01"), None),
+            test_page(Some("2018-03-01"), None),
+            test_page(Some("2018-01-01"), None),
+        ];
+
+        // `pages[0]` is deliberately not the most recent, simulating `sort_by = "weight"`
+        // or `sort_by = "none"` alongside `generate_feed = true`
+        assert_eq!(super::latest_update(&pages), Some("2018-03-01T00:00:00Z".to_string()));
+    }
+
+    #[test]
+    fn latest_update_ignores_undated_pages_and_handles_none() {
+        let pages = vec![test_page(None, None), test_page(None, None)];
+        assert_eq!(super::latest_update(&pages), None);
+
+        assert_eq!(super::latest_update(&[]), None);
+    }
+
+    #[test]
+    fn feed_entry_formats_date_as_rfc3339() {
+        let page = test_page(Some("2018-01-01"), None);
+        let entry = super::FeedEntry::from_page(&page);
+
+        assert_eq!(entry.date, Some("2018-01-01T00:00:00Z".to_string()));
+    }
+}
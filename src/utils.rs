@@ -0,0 +1,35 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+
+use errors::{Result, ResultExt};
+
+/// Read a file into a String
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<String> {
+    let path = path.as_ref();
+    let mut content = String::new();
+    File::open(path)
+        .chain_err(|| format!("Failed to open '{}'", path.display()))?
+        .read_to_string(&mut content)
+        .chain_err(|| format!("Failed to read '{}'", path.display()))?;
+
+    Ok(content)
+}
+
+/// Returns the components of a `.md` or `_index.md` file path, relative to the `content`
+/// directory and with the filename itself stripped
+pub fn find_content_components<P: AsRef<Path>>(path: P) -> Vec<String> {
+    let path = path.as_ref();
+    let mut components = path
+        .parent()
+        .unwrap()
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+
+    if let Some(pos) = components.iter().position(|c| c == "content") {
+        components = components.split_off(pos + 1);
+    }
+
+    components
+}